@@ -1,6 +1,11 @@
 use std::any::Any;
 use std::fmt::Debug;
+use std::str::FromStr;
 
+// Renders a `Default:` line for the first type in the given list that the
+// value downcasts to. The list passed in by `Flag`'s `Debug` impl is the
+// registered set of renderable types; the parser's runtime type dispatch
+// (`store_raw`/`store_repeated`) keeps its own list of parseable types.
 #[allow(unused_macros)]
 macro_rules! match_value {
     ($value:expr, $f:expr, $(rule = $type:ty;)+) => {
@@ -8,7 +13,7 @@ macro_rules! match_value {
             if let Some(v) = $value.downcast_ref::<$type>() {
                 write!($f, " | Default: `{}`", v)?;
             } else
-        )+ 
+        )+
         {
             write!($f, " | Default: [unknown type]")?;
         }
@@ -35,31 +40,82 @@ macro_rules! debug_match {
 pub mod flag {
     use super::*;
 
+    /// Errors from building, parsing, or dispatching flags. Each variant
+    /// carries the flag name or raw token it was raised for, and
+    /// `ParseFailed` chains the underlying `FromStr` error like `anyhow`
+    /// chains its sources.
     #[allow(dead_code)]
+    #[derive(Debug)]
     pub enum FlagError {
-        NoValue,
-        InvalidFlag,
-        MissingArgument,
+        NoValue { flag: String },
+        InvalidFlag(String),
+        MissingArgument { flag: String },
+        ParseFailed {
+            flag: String,
+            source: Box<dyn std::error::Error + 'static>,
+        },
     }
 
-    #[allow(unreachable_patterns)]
-    impl std::fmt::Debug for FlagError {
+    impl std::fmt::Display for FlagError {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            use FlagError::*;
-            debug_match!(f, *self, // Dereference self to match enum variants
-                rule = NoValue, "No value provided";
-                rule = InvalidFlag, "Invalid flag";
-                rule = MissingArgument, "Missing Argument";
-            )
+            match self {
+                FlagError::NoValue { flag } => {
+                    write!(f, "{} has no default value to overwrite", flag)
+                }
+                FlagError::InvalidFlag(token) => write!(f, "invalid flag: {}", token),
+                FlagError::MissingArgument { flag } => {
+                    write!(f, "missing argument for {}", flag)
+                }
+                FlagError::ParseFailed { flag, source } => {
+                    write!(f, "failed to parse {}: {}", flag, source)
+                }
+            }
         }
     }
 
+    impl std::error::Error for FlagError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                FlagError::ParseFailed { source, .. } => Some(source.as_ref()),
+                _ => None,
+            }
+        }
+    }
+
+    /// Attaches the name of the flag being processed to a lower-level
+    /// error, turning it into a `FlagError::ParseFailed` — the `anyhow`
+    /// `.context()` pattern, specialized to this crate's error type.
+    pub trait Context<T> {
+        fn context(self, flag: &str) -> Result<T, FlagError>;
+    }
+
+    impl<T, E: std::error::Error + 'static> Context<T> for Result<T, E> {
+        fn context(self, flag: &str) -> Result<T, FlagError> {
+            self.map_err(|source| FlagError::ParseFailed {
+                flag: flag.to_string(),
+                source: Box::new(source),
+            })
+        }
+    }
+
+    /// Whether a flag may be omitted, must appear, or may appear more than
+    /// once. Mirrors xflags' `Optional`/`Required`/repeated distinction.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Arity {
+        Optional,
+        Required,
+        Repeated,
+    }
+
     pub struct Flag<'a> {
         pub name: &'a str,
         pub args: &'a [&'a str],
         pub desc: &'a str,
         pub notes: Option<&'a str>,
         pub value: Option<Box<dyn Any>>,
+        pub arity: Arity,
+        pub values: Vec<Box<dyn Any>>,
+        pub seen: bool,
     }
 
     impl<'a> Debug for Flag<'a> {
@@ -72,12 +128,21 @@ pub mod flag {
             if let Some(notes) = self.notes {
                 write!(f, " | {}", notes)?;
             }
+            match self.arity {
+                Arity::Required => write!(f, " | (required)")?,
+                Arity::Repeated => write!(f, " | (repeatable)")?,
+                Arity::Optional => {}
+            }
             if let Some(value) = self.value.as_ref() {
                 match_value!( value, f,
                     rule = &'static str;
                     rule = &'static bool;
                     rule = &'static i32;
                     rule = &'static f32;
+                    rule = String;
+                    rule = bool;
+                    rule = i32;
+                    rule = f32;
                 )
             }
             Ok(())
@@ -92,6 +157,7 @@ pub mod flag {
             desc: &'a str,
             notes: Option<&'a str>,
             value: Option<Box<dyn Any>>,
+            arity: Arity,
         ) -> Self {
             Self {
                 name,
@@ -99,6 +165,9 @@ pub mod flag {
                 desc,
                 notes, // Note examples: `To be deprecated`, `Not implimented`, `Developer use only`
                 value,
+                arity,
+                values: Vec::new(),
+                seen: false,
             }
         }
 
@@ -116,16 +185,602 @@ pub mod flag {
 
         pub fn set_value(&mut self, value: &'static dyn Any) -> Result<(), FlagError> {
             if self.value.is_none() {
-                return Err(FlagError::NoValue);
+                return Err(FlagError::NoValue {
+                    flag: self.name.to_string(),
+                });
             }
             self.value = Some(Box::new(value));
             Ok(())
         }
 
+        /// Parses `raw` as `T` and stores it as the flag's value, overwriting
+        /// whatever was there before. Unlike `set_value`, this stores `T`
+        /// itself rather than a `&'static` reference to it, so the result of
+        /// a runtime parse (something that can't be `'static`-borrowed) can
+        /// actually be kept. A parse failure is reported as
+        /// `FlagError::ParseFailed`, chaining the `FromStr` error as its
+        /// `source()`.
+        pub fn parse_value<T>(&mut self, raw: &str) -> Result<(), FlagError>
+        where
+            T: FromStr + 'static,
+            T::Err: std::error::Error + 'static,
+        {
+            let parsed = raw.parse::<T>().context(self.name)?;
+            self.value = Some(Box::new(parsed));
+            Ok(())
+        }
+
+        pub fn get_as<T: 'static>(&self) -> Option<&T> {
+            self.value.as_ref()?.downcast_ref::<T>()
+        }
+
+        /// The accumulated values of a `Repeated` flag, in the order they
+        /// were encountered on the command line.
+        pub fn get_values(&self) -> &[Box<dyn Any>] {
+            &self.values
+        }
+
         pub fn is_in(&self, s: &str) -> bool {
             self.args.contains(&s)
         }
+
+        /// Whether this flag was matched at least once during parsing.
+        /// Tracked independently of `value`/`values` so a bare switch (no
+        /// default, no accumulated values) can still report its presence.
+        pub fn was_seen(&self) -> bool {
+            self.seen
+        }
+    }
+
+    #[allow(dead_code)]
+    pub struct FlagSet<'a> {
+        flags: Vec<Flag<'a>>,
     }
+
+    #[allow(dead_code)]
+    impl<'a> FlagSet<'a> {
+        pub fn new(flags: Vec<Flag<'a>>) -> Self {
+            Self { flags }
+        }
+
+        pub fn flags(&self) -> &[Flag<'a>] {
+            &self.flags
+        }
+
+        /// Walks `args`, matching each token against the registered flags via
+        /// `Flag::is_in`. An `Optional`/`Required` flag that already carries
+        /// a value (i.e. was constructed with a default) is treated as
+        /// argument-taking and consumes the next token; one with no value is
+        /// treated as a bare switch. `Repeated` flags always consume the next
+        /// token and accumulate every occurrence instead of overwriting. Anything that doesn't match a known flag and
+        /// doesn't look like a flag is returned as a leftover positional
+        /// argument. Once `args` is exhausted, any `Required` flag that
+        /// never appeared yields `FlagError::MissingArgument`.
+        pub fn parse(
+            &mut self,
+            args: impl Iterator<Item = String>,
+        ) -> Result<Vec<String>, FlagError> {
+            let mut positional = Vec::new();
+            let mut args = args.into_iter();
+
+            while let Some(token) = args.next() {
+                match self.flags.iter_mut().find(|flag| flag.is_in(&token)) {
+                    Some(flag) => {
+                        flag.seen = true;
+                        consume_flag_value(flag, &mut args)?;
+                    }
+                    None => {
+                        if token.starts_with('-') {
+                            return Err(FlagError::InvalidFlag(token));
+                        }
+                        positional.push(token);
+                    }
+                }
+            }
+
+            check_required(&self.flags)?;
+
+            Ok(positional)
+        }
+
+        /// Renders an aligned, sectioned usage screen: a synopsis line
+        /// followed by one line per flag, showing its aliases, a `<VALUE>`
+        /// placeholder when it takes an argument, its description, and any
+        /// `notes` in brackets.
+        pub fn help(&self) -> String {
+            let alias_width = self
+                .flags
+                .iter()
+                .map(|flag| flag_alias_label(flag).len())
+                .max()
+                .unwrap_or(0);
+
+            let mut out = String::from("Usage: [OPTIONS] [ARGS]...\n\nFlags:\n");
+            for flag in &self.flags {
+                out.push_str(&format_flag_line(flag, alias_width));
+                out.push('\n');
+            }
+            out
+        }
+
+        /// Convenience wrapper around `parse` that reads `std::env::args`
+        /// (skipping the binary name). Prints `help()` and exits `0` if
+        /// `-h`/`--help` is present, and exits the process with status `1`
+        /// on any `FlagError`, printing it first.
+        pub fn from_env_or_exit(&mut self) -> Vec<String> {
+            let args: Vec<String> = std::env::args().skip(1).collect();
+
+            if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+                println!("{}", self.help());
+                std::process::exit(0);
+            }
+
+            match self.parse(args.into_iter()) {
+                Ok(positional) => positional,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    /// Parses `raw` into whichever registered type `flag`'s current default
+    /// value already holds, then overwrites it via `Flag::parse_value`.
+    /// Falls back to `String` for flags with no recognized default type.
+    fn store_raw(flag: &mut Flag<'_>, raw: &str) -> Result<(), FlagError> {
+        match flag.value.as_deref() {
+            Some(v) if v.is::<i32>() => flag.parse_value::<i32>(raw),
+            Some(v) if v.is::<f32>() => flag.parse_value::<f32>(raw),
+            Some(v) if v.is::<bool>() => flag.parse_value::<bool>(raw),
+            _ => flag.parse_value::<String>(raw),
+        }
+    }
+
+    /// Like `store_raw`, but appends to `Flag::values` instead of
+    /// overwriting `Flag::value` — the accumulation behavior for
+    /// `Arity::Repeated` flags.
+    fn store_repeated(flag: &mut Flag<'_>, raw: &str) -> Result<(), FlagError> {
+        fn push<T>(flag: &mut Flag<'_>, raw: &str) -> Result<(), FlagError>
+        where
+            T: FromStr + 'static,
+            T::Err: std::error::Error + 'static,
+        {
+            let parsed = raw.parse::<T>().context(flag.name)?;
+            flag.values.push(Box::new(parsed));
+            Ok(())
+        }
+
+        match flag.value.as_deref() {
+            Some(v) if v.is::<i32>() => push::<i32>(flag, raw),
+            Some(v) if v.is::<f32>() => push::<f32>(flag, raw),
+            Some(v) if v.is::<bool>() => push::<bool>(flag, raw),
+            _ => push::<String>(flag, raw),
+        }
+    }
+
+    /// Consumes the token following a matched flag (if any) and stores it
+    /// according to the flag's `Arity`. `Repeated` flags always expect a
+    /// value and accumulate it; `Optional`/`Required` flags only consume a
+    /// value when constructed with a default witnessing the expected type.
+    /// Shared by `FlagSet::parse` and `Command::run`.
+    fn consume_flag_value<I: Iterator<Item = String>>(
+        flag: &mut Flag<'_>,
+        args: &mut I,
+    ) -> Result<(), FlagError> {
+        let missing_argument = |flag: &Flag<'_>| FlagError::MissingArgument {
+            flag: flag.name.to_string(),
+        };
+
+        match flag.arity {
+            Arity::Repeated => {
+                let raw = args.next().ok_or_else(|| missing_argument(flag))?;
+                store_repeated(flag, &raw)
+            }
+            Arity::Optional | Arity::Required => {
+                if flag.value.is_some() {
+                    let raw = args.next().ok_or_else(|| missing_argument(flag))?;
+                    store_raw(flag, &raw)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Returns `FlagError::MissingArgument` if any `Required` flag in
+    /// `flags` was never marked `seen` while parsing.
+    fn check_required(flags: &[Flag<'_>]) -> Result<(), FlagError> {
+        let missing = flags
+            .iter()
+            .find(|flag| flag.arity == Arity::Required && !flag.seen);
+
+        if let Some(flag) = missing {
+            return Err(FlagError::MissingArgument {
+                flag: flag.name.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The aliases column for a flag's help line, e.g. `-o, --output
+    /// <VALUE>` — the `<VALUE>` placeholder is appended when the flag takes
+    /// an argument: `Repeated` flags always consume one (see
+    /// `consume_flag_value`), while `Optional`/`Required` flags only do so
+    /// when constructed with a default witnessing the expected type.
+    fn flag_alias_label(flag: &Flag<'_>) -> String {
+        let mut label = flag.args.join(", ");
+        if flag.arity == Arity::Repeated || flag.value.is_some() {
+            label.push_str(" <VALUE>");
+        }
+        label
+    }
+
+    /// One aligned help line for `flag`: its alias label padded to
+    /// `alias_width`, its description, and any `notes` appended in
+    /// brackets. Shared by `FlagSet::help` and `Command::help`.
+    fn format_flag_line(flag: &Flag<'_>, alias_width: usize) -> String {
+        let label = flag_alias_label(flag);
+        let mut line = format!("  {:<width$}  {}", label, flag.desc, width = alias_width);
+        if let Some(notes) = flag.notes {
+            line.push_str(&format!(" [{}]", notes));
+        }
+        line
+    }
+
+    /// A node in a `git`-style subcommand tree: a name, the flags it
+    /// accepts, the positional arguments left over after parsing, and any
+    /// nested subcommands. `run` walks the token stream, matching flags
+    /// against `self`, and the first non-flag token against a child's name
+    /// to recurse into it with the remaining tokens — mirroring how xflags
+    /// threads a `Cmd` down into its matched subcommand.
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    pub struct Command<'a> {
+        name: &'a str,
+        flags: Vec<Flag<'a>>,
+        positional: Vec<String>,
+        children: Vec<Command<'a>>,
+    }
+
+    #[allow(dead_code)]
+    impl<'a> Command<'a> {
+        pub fn new(name: &'a str, flags: Vec<Flag<'a>>, children: Vec<Command<'a>>) -> Self {
+            Self {
+                name,
+                flags,
+                positional: Vec::new(),
+                children,
+            }
+        }
+
+        pub fn name(&self) -> &'a str {
+            self.name
+        }
+
+        pub fn flags(&self) -> &[Flag<'a>] {
+            &self.flags
+        }
+
+        pub fn positional(&self) -> &[String] {
+            &self.positional
+        }
+
+        pub fn children(&self) -> &[Command<'a>] {
+            &self.children
+        }
+
+        /// Consumes `self` and `args`, returning the deepest command reached
+        /// once a child matches a positional token, holding that command's
+        /// own parsed flags (with `Arity` enforced, see `FlagSet::parse`)
+        /// and leftover positional arguments.
+        pub fn run(mut self, args: impl Iterator<Item = String>) -> Result<Self, FlagError> {
+            let mut args = args;
+
+            while let Some(token) = args.next() {
+                if let Some(flag) = self.flags.iter_mut().find(|flag| flag.is_in(&token)) {
+                    flag.seen = true;
+                    consume_flag_value(flag, &mut args)?;
+                    continue;
+                }
+
+                if self.positional.is_empty() {
+                    if let Some(pos) = self.children.iter().position(|child| child.name == token) {
+                        check_required(&self.flags)?;
+                        let child = self.children.swap_remove(pos);
+                        return child.run(args);
+                    }
+                }
+
+                if token.starts_with('-') {
+                    return Err(FlagError::InvalidFlag(token));
+                }
+
+                self.positional.push(token);
+            }
+
+            check_required(&self.flags)?;
+
+            Ok(self)
+        }
+
+        /// Renders a usage screen for this command: a synopsis line naming
+        /// `self` (and `[COMMAND]` when it has children), an aligned
+        /// `Flags:` section, and a `Commands:` section listing subcommand
+        /// names.
+        pub fn help(&self) -> String {
+            let mut out = format!("Usage: {} [OPTIONS]", self.name);
+            if !self.children.is_empty() {
+                out.push_str(" [COMMAND]");
+            }
+            out.push_str("\n\n");
+
+            if !self.flags.is_empty() {
+                let alias_width = self
+                    .flags
+                    .iter()
+                    .map(|flag| flag_alias_label(flag).len())
+                    .max()
+                    .unwrap_or(0);
+
+                out.push_str("Flags:\n");
+                for flag in &self.flags {
+                    out.push_str(&format_flag_line(flag, alias_width));
+                    out.push('\n');
+                }
+            }
+
+            if !self.children.is_empty() {
+                out.push_str("\nCommands:\n");
+                for child in &self.children {
+                    out.push_str(&format!("  {}\n", child.name));
+                }
+            }
+
+            out
+        }
+    }
+}
+
+/// A declarative front-end over [`flag::FlagSet`]: describe a command's
+/// flags once and get back a concrete struct whose fields already hold the
+/// parsed values, instead of pulling them back out via `Flag::get_as`/
+/// `get_values`. Mirrors xflags' headline feature.
+///
+/// Each entry is one of:
+///
+/// - `required $field: $ty;` — a positional argument, consumed in
+///   declaration order from whatever's left over after flags are parsed.
+///   Becomes a `$ty` field; missing one is a `FlagError::MissingArgument`.
+/// - `optional -$short, --$long;` — a bare switch. Becomes a `bool` field
+///   that's `true` iff the flag appeared.
+/// - `optional -$short, --$long: $ty;` — an optional value flag. Becomes
+///   an `Option<$ty>` field.
+/// - `repeated -$short, --$long: $ty;` — a repeatable value flag. Becomes
+///   a `Vec<$ty>` field, accumulating every occurrence.
+///
+/// `$ty` must implement `FromStr` with an `Error: std::error::Error`, the
+/// same bound `Flag::parse_value` requires. Option and repeated values are
+/// first stored as raw `String`s by the `FlagSet`, then each is parsed into
+/// `$ty` and `.context()`-chained the same way a hand-written caller would.
+///
+/// ```ignore
+/// flags! {
+///     cmd App {
+///         required input: PathBuf;
+///         optional -v, --verbose;
+///         repeated -I, --include: String;
+///     }
+/// }
+///
+/// let app = App::from_env()?;
+/// ```
+#[macro_export]
+macro_rules! flags {
+    (cmd $name:ident { $($body:tt)* }) => {
+        $crate::flags!(@parse $name;
+            positional = [];
+            switches = [];
+            options = [];
+            repeated = [];
+            $($body)*
+        );
+    };
+
+    (@parse $name:ident;
+        positional = [$($pos:tt)*];
+        switches = [$($sw:tt)*];
+        options = [$($opt:tt)*];
+        repeated = [$($rep:tt)*];
+        required $field:ident : $ty:ty ; $($rest:tt)*
+    ) => {
+        $crate::flags!(@parse $name;
+            positional = [$($pos)* ($field, $ty)];
+            switches = [$($sw)*];
+            options = [$($opt)*];
+            repeated = [$($rep)*];
+            $($rest)*
+        );
+    };
+
+    (@parse $name:ident;
+        positional = [$($pos:tt)*];
+        switches = [$($sw:tt)*];
+        options = [$($opt:tt)*];
+        repeated = [$($rep:tt)*];
+        optional - $short:ident , - - $long:ident : $ty:ty ; $($rest:tt)*
+    ) => {
+        $crate::flags!(@parse $name;
+            positional = [$($pos)*];
+            switches = [$($sw)*];
+            options = [$($opt)* ($short, $long, $ty)];
+            repeated = [$($rep)*];
+            $($rest)*
+        );
+    };
+
+    (@parse $name:ident;
+        positional = [$($pos:tt)*];
+        switches = [$($sw:tt)*];
+        options = [$($opt:tt)*];
+        repeated = [$($rep:tt)*];
+        optional - $short:ident , - - $long:ident ; $($rest:tt)*
+    ) => {
+        $crate::flags!(@parse $name;
+            positional = [$($pos)*];
+            switches = [$($sw)* ($short, $long)];
+            options = [$($opt)*];
+            repeated = [$($rep)*];
+            $($rest)*
+        );
+    };
+
+    (@parse $name:ident;
+        positional = [$($pos:tt)*];
+        switches = [$($sw:tt)*];
+        options = [$($opt:tt)*];
+        repeated = [$($rep:tt)*];
+        repeated - $short:ident , - - $long:ident : $ty:ty ; $($rest:tt)*
+    ) => {
+        $crate::flags!(@parse $name;
+            positional = [$($pos)*];
+            switches = [$($sw)*];
+            options = [$($opt)*];
+            repeated = [$($rep)* ($short, $long, $ty)];
+            $($rest)*
+        );
+    };
+
+    (@parse $name:ident;
+        positional = [$(($pos_field:ident, $pos_ty:ty))*];
+        switches = [$(($sw_short:ident, $sw_long:ident))*];
+        options = [$(($opt_short:ident, $opt_long:ident, $opt_ty:ty))*];
+        repeated = [$(($rep_short:ident, $rep_long:ident, $rep_ty:ty))*];
+    ) => {
+        #[derive(Debug)]
+        pub struct $name {
+            $(pub $pos_field: $pos_ty,)*
+            $(pub $sw_long: bool,)*
+            $(pub $opt_long: Option<$opt_ty>,)*
+            $(pub $rep_long: Vec<$rep_ty>,)*
+        }
+
+        #[allow(dead_code)]
+        impl $name {
+            #[allow(clippy::vec_init_then_push)]
+            pub fn from_args(
+                args: impl Iterator<Item = String>,
+            ) -> Result<Self, $crate::flag::FlagError> {
+                use $crate::flag::{Arity, Context, Flag, FlagSet};
+
+                let mut flags = Vec::new();
+                $(
+                    flags.push(Flag::new(
+                        stringify!($sw_long),
+                        &[concat!("-", stringify!($sw_short)), concat!("--", stringify!($sw_long))],
+                        concat!(stringify!($sw_long), " flag"),
+                        None,
+                        None,
+                        Arity::Optional,
+                    ));
+                )*
+                $(
+                    flags.push(Flag::new(
+                        stringify!($opt_long),
+                        &[concat!("-", stringify!($opt_short)), concat!("--", stringify!($opt_long))],
+                        concat!(stringify!($opt_long), " flag"),
+                        None,
+                        Some(Box::new(String::new())),
+                        Arity::Optional,
+                    ));
+                )*
+                $(
+                    flags.push(Flag::new(
+                        stringify!($rep_long),
+                        &[concat!("-", stringify!($rep_short)), concat!("--", stringify!($rep_long))],
+                        concat!(stringify!($rep_long), " flag"),
+                        None,
+                        Some(Box::new(String::new())),
+                        Arity::Repeated,
+                    ));
+                )*
+
+                let mut set = FlagSet::new(flags);
+                let mut positional = set.parse(args)?.into_iter();
+
+                $(
+                    let $pos_field: $pos_ty = positional
+                        .next()
+                        .ok_or_else(|| $crate::flag::FlagError::MissingArgument {
+                            flag: stringify!($pos_field).to_string(),
+                        })?
+                        .parse::<$pos_ty>()
+                        .context(stringify!($pos_field))?;
+                )*
+
+                $(
+                    let $sw_long = set
+                        .flags()
+                        .iter()
+                        .find(|flag| flag.get_name() == stringify!($sw_long))
+                        .map(|flag| flag.was_seen())
+                        .unwrap_or(false);
+                )*
+
+                $(
+                    let $opt_long: Option<$opt_ty> = {
+                        let flag = set
+                            .flags()
+                            .iter()
+                            .find(|flag| flag.get_name() == stringify!($opt_long))
+                            .expect("flag declared above");
+                        if flag.was_seen() {
+                            Some(
+                                flag.get_as::<String>()
+                                    .expect("option flags store their raw value as a String")
+                                    .parse::<$opt_ty>()
+                                    .context(stringify!($opt_long))?,
+                            )
+                        } else {
+                            None
+                        }
+                    };
+                )*
+
+                $(
+                    let $rep_long: Vec<$rep_ty> = {
+                        let flag = set
+                            .flags()
+                            .iter()
+                            .find(|flag| flag.get_name() == stringify!($rep_long))
+                            .expect("flag declared above");
+                        flag.get_values()
+                            .iter()
+                            .map(|value| {
+                                value
+                                    .downcast_ref::<String>()
+                                    .expect("repeated flags store their raw values as a String")
+                                    .parse::<$rep_ty>()
+                                    .context(stringify!($rep_long))
+                            })
+                            .collect::<Result<Vec<_>, _>>()?
+                    };
+                )*
+
+                Ok(Self {
+                    $($pos_field,)*
+                    $($sw_long,)*
+                    $($opt_long,)*
+                    $($rep_long,)*
+                })
+            }
+
+            pub fn from_env() -> Result<Self, $crate::flag::FlagError> {
+                Self::from_args(std::env::args().skip(1))
+            }
+        }
+    };
 }
 
 mod note {
@@ -156,6 +811,7 @@ mod err_note {
     pub enum ErrType {
         Exit,
         Assertion,
+        Help,
     }
 
     impl Debug for ErrType {
@@ -163,8 +819,9 @@ mod err_note {
             use ErrType::*;
 
             debug_match!(f, *self,
-                rule = Exit, "EXIT";
-                rule = Assertion, "ASSERTION";
+                rule = Exit, "EXIT ERROR";
+                rule = Assertion, "ASSERTION ERROR";
+                rule = Help, "HELP";
             )
         }
     }
@@ -177,7 +834,7 @@ mod err_note {
 
     impl<'a> Debug for ErrNote<'a> {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result<> {
-            write!(f, "[{:?} ERROR]: {}", self.type_, self.err_note)
+            write!(f, "[{:?}]: {}", self.type_, self.err_note)
         }
     }
 
@@ -194,9 +851,15 @@ mod err_note {
         }
 
         pub fn exit(&self) {
+            self.exit_with(1);
+        }
+
+        /// Like `exit`, but lets the caller pick the process exit code —
+        /// e.g. `0` for a successful `--help` screen rather than an error.
+        pub fn exit_with(&self, code: i32) {
             if self.exit {
                 println!("{:?}", self);
-                process::exit(1);
+                process::exit(code);
             }
         }
 
@@ -219,6 +882,9 @@ mod tests {
             desc: "A test flag",
             notes: Some("Optional notes"),
             value: Some(Box::new(42) as Box<dyn Any>),
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
         };
 
         assert_eq!(flag.name, "test");
@@ -246,6 +912,9 @@ mod tests {
             desc: "Display help information",
             notes: None,
             value: None,
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
         };
 
         assert_eq!(flag.name, "help");
@@ -263,6 +932,9 @@ mod tests {
             desc: "Specify the output file",
             notes: None,
             value: Some(Box::new("output.txt") as Box<dyn Any>),
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
         };
 
         let debug_output = format!("{:?}", flag);
@@ -284,6 +956,9 @@ mod tests {
             desc: "Enable verbose output",
             notes: Some("Useful for debugging"),
             value: Some(Box::new(true) as Box<dyn Any>),
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
         };
 
         if let Some(value) = flag.value.as_ref() {
@@ -296,5 +971,387 @@ mod tests {
             panic!("Flag value is missing!");
         }
     }
+
+    #[test]
+    fn test_flag_set_parses_value_and_positional() {
+        let mut set = FlagSet::new(vec![Flag {
+            name: "output",
+            args: &["-o", "--output"],
+            desc: "Specify the output file",
+            notes: None,
+            value: Some(Box::new("default.txt") as Box<dyn Any>),
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
+        }]);
+
+        let rest = set
+            .parse(vec!["-o".to_string(), "out.txt".to_string(), "input.rs".to_string()].into_iter())
+            .expect("parse should succeed");
+
+        assert_eq!(rest, vec!["input.rs".to_string()]);
+        assert_eq!(
+            set.flags()[0].get_as::<String>(),
+            Some(&"out.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_flag_set_coerces_value_to_default_type() {
+        let mut set = FlagSet::new(vec![Flag {
+            name: "count",
+            args: &["-c", "--count"],
+            desc: "Number of retries",
+            notes: None,
+            value: Some(Box::new(1i32) as Box<dyn Any>),
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
+        }]);
+
+        set.parse(vec!["-c".to_string(), "5".to_string()].into_iter())
+            .expect("parse should succeed");
+
+        assert_eq!(set.flags()[0].get_as::<i32>(), Some(&5));
+    }
+
+    #[test]
+    fn test_flag_parse_value_rejects_bad_input() {
+        let mut flag = Flag {
+            name: "count",
+            args: &["-c", "--count"],
+            desc: "Number of retries",
+            notes: None,
+            value: Some(Box::new(1i32) as Box<dyn Any>),
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
+        };
+
+        let err = flag.parse_value::<i32>("not-a-number").unwrap_err();
+        assert!(matches!(err, FlagError::ParseFailed { .. }));
+    }
+
+    #[test]
+    fn test_flag_error_display_chains_source() {
+        use std::error::Error;
+
+        let mut flag = Flag {
+            name: "--count",
+            args: &["-c", "--count"],
+            desc: "Number of retries",
+            notes: None,
+            value: Some(Box::new(1i32) as Box<dyn Any>),
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
+        };
+
+        let err = flag.parse_value::<i32>("nope").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "failed to parse --count: invalid digit found in string"
+        );
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn test_flag_set_rejects_unknown_flag() {
+        let mut set = FlagSet::new(vec![Flag {
+            name: "verbose",
+            args: &["-v", "--verbose"],
+            desc: "Enable verbose output",
+            notes: None,
+            value: None,
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
+        }]);
+
+        let err = set
+            .parse(vec!["--bogus".to_string()].into_iter())
+            .unwrap_err();
+
+        assert!(matches!(err, FlagError::InvalidFlag(token) if token == "--bogus"));
+    }
+
+    #[test]
+    fn test_flag_set_missing_argument() {
+        let mut set = FlagSet::new(vec![Flag {
+            name: "output",
+            args: &["-o", "--output"],
+            desc: "Specify the output file",
+            notes: None,
+            value: Some(Box::new("default.txt") as Box<dyn Any>),
+            arity: Arity::Optional,
+            values: Vec::new(),
+            seen: false,
+        }]);
+
+        let err = set.parse(vec!["-o".to_string()].into_iter()).unwrap_err();
+
+        assert!(matches!(err, FlagError::MissingArgument { .. }));
+    }
+
+    #[test]
+    fn test_command_dispatches_to_deepest_child() {
+        let add = Command::new(
+            "add",
+            vec![Flag {
+                name: "url",
+                args: &["-u", "--url"],
+                desc: "Remote URL",
+                notes: None,
+                value: Some(Box::new(String::new()) as Box<dyn Any>),
+                arity: Arity::Optional,
+                values: Vec::new(),
+                seen: false,
+            }],
+            vec![],
+        );
+        let remote = Command::new("remote", vec![], vec![add]);
+        let app = Command::new("app", vec![], vec![remote]);
+
+        let leaf = app
+            .run(
+                vec![
+                    "remote".to_string(),
+                    "add".to_string(),
+                    "-u".to_string(),
+                    "https://example.com".to_string(),
+                    "origin".to_string(),
+                ]
+                .into_iter(),
+            )
+            .expect("run should succeed");
+
+        assert_eq!(leaf.name(), "add");
+        assert_eq!(leaf.positional(), &["origin".to_string()]);
+        assert_eq!(
+            leaf.flags()[0].get_as::<String>(),
+            Some(&"https://example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_command_enforces_required_flag_before_dispatching_to_child() {
+        let add = Command::new("add", vec![], vec![]);
+        let remote = Command::new("remote", vec![], vec![add]);
+        let app = Command::new(
+            "app",
+            vec![Flag {
+                name: "config",
+                args: &["-c", "--config"],
+                desc: "Config file",
+                notes: None,
+                value: Some(Box::new(String::new()) as Box<dyn Any>),
+                arity: Arity::Required,
+                values: Vec::new(),
+                seen: false,
+            }],
+            vec![remote],
+        );
+
+        let err = app
+            .run(vec!["remote".to_string(), "add".to_string()].into_iter())
+            .unwrap_err();
+
+        assert!(matches!(err, FlagError::MissingArgument { flag } if flag == "config"));
+    }
+
+    #[test]
+    fn test_command_with_no_matching_child_stays_at_root() {
+        let cmd = Command::new("app", vec![], vec![]);
+
+        let leaf = cmd
+            .run(vec!["input.rs".to_string()].into_iter())
+            .expect("run should succeed");
+
+        assert_eq!(leaf.name(), "app");
+        assert_eq!(leaf.positional(), &["input.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_required_flag_missing_errors() {
+        let mut set = FlagSet::new(vec![Flag {
+            name: "input",
+            args: &["-i", "--input"],
+            desc: "Input file",
+            notes: None,
+            value: Some(Box::new(String::new()) as Box<dyn Any>),
+            arity: Arity::Required,
+            values: Vec::new(),
+            seen: false,
+        }]);
+
+        let err = set.parse(vec![].into_iter()).unwrap_err();
+
+        assert!(matches!(err, FlagError::MissingArgument { .. }));
+    }
+
+    #[test]
+    fn test_repeated_flag_accumulates_values() {
+        let mut set = FlagSet::new(vec![Flag {
+            name: "include",
+            args: &["-I", "--include"],
+            desc: "Add an include path",
+            notes: None,
+            value: Some(Box::new(String::new()) as Box<dyn Any>),
+            arity: Arity::Repeated,
+            values: Vec::new(),
+            seen: false,
+        }]);
+
+        set.parse(
+            vec![
+                "-I".to_string(),
+                "path1".to_string(),
+                "-I".to_string(),
+                "path2".to_string(),
+            ]
+            .into_iter(),
+        )
+        .expect("parse should succeed");
+
+        let values = set.flags()[0].get_values();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].downcast_ref::<String>(), Some(&"path1".to_string()));
+        assert_eq!(values[1].downcast_ref::<String>(), Some(&"path2".to_string()));
+    }
+
+    #[test]
+    fn test_debug_shows_arity_annotations() {
+        let required = Flag {
+            name: "input",
+            args: &["-i"],
+            desc: "Input file",
+            notes: None,
+            value: Some(Box::new(String::new()) as Box<dyn Any>),
+            arity: Arity::Required,
+            values: Vec::new(),
+            seen: false,
+        };
+        let repeated = Flag {
+            name: "include",
+            args: &["-I"],
+            desc: "Include path",
+            notes: None,
+            value: Some(Box::new(String::new()) as Box<dyn Any>),
+            arity: Arity::Repeated,
+            values: Vec::new(),
+            seen: false,
+        };
+
+        assert!(format!("{:?}", required).contains("(required)"));
+        assert!(format!("{:?}", repeated).contains("(repeatable)"));
+    }
+
+    #[test]
+    fn test_flag_set_help_lists_flags() {
+        let set = FlagSet::new(vec![
+            Flag {
+                name: "output",
+                args: &["-o", "--output"],
+                desc: "Specify the output file",
+                notes: None,
+                value: Some(Box::new(String::new()) as Box<dyn Any>),
+                arity: Arity::Optional,
+                values: Vec::new(),
+                seen: false,
+            },
+            Flag {
+                name: "verbose",
+                args: &["-v", "--verbose"],
+                desc: "Enable verbose output",
+                notes: Some("Useful for debugging"),
+                value: None,
+                arity: Arity::Optional,
+                values: Vec::new(),
+                seen: false,
+            },
+        ]);
+
+        let help = set.help();
+
+        assert!(help.contains("-o, --output <VALUE>"));
+        assert!(help.contains("Specify the output file"));
+        assert!(help.contains("-v, --verbose"));
+        assert!(help.contains("[Useful for debugging]"));
+    }
+
+    #[test]
+    fn test_flag_set_help_shows_value_placeholder_for_repeated_without_default() {
+        let set = FlagSet::new(vec![Flag {
+            name: "include",
+            args: &["-I", "--include"],
+            desc: "Add an include path",
+            notes: None,
+            value: None,
+            arity: Arity::Repeated,
+            values: Vec::new(),
+            seen: false,
+        }]);
+
+        let help = set.help();
+
+        assert!(help.contains("-I, --include <VALUE>"));
+    }
+
+    #[test]
+    fn test_command_help_lists_flags_and_children() {
+        let add = Command::new("add", vec![], vec![]);
+        let remote = Command::new("remote", vec![], vec![add]);
+
+        let help = remote.help();
+
+        assert!(help.contains("Usage: remote [OPTIONS] [COMMAND]"));
+        assert!(help.contains("Commands:"));
+        assert!(help.contains("add"));
+    }
+
+    flags! {
+        cmd AppFlags {
+            required input: std::path::PathBuf;
+            optional -v, --verbose;
+            repeated -I, --include: String;
+        }
+    }
+
+    #[test]
+    fn test_flags_macro_parses_positional_switch_and_repeated() {
+        let app = AppFlags::from_args(
+            vec![
+                "-v".to_string(),
+                "-I".to_string(),
+                "path1".to_string(),
+                "-I".to_string(),
+                "path2".to_string(),
+                "input.rs".to_string(),
+            ]
+            .into_iter(),
+        )
+        .expect("from_args should succeed");
+
+        assert_eq!(app.input, std::path::PathBuf::from("input.rs"));
+        assert!(app.verbose);
+        assert_eq!(app.include, vec!["path1".to_string(), "path2".to_string()]);
+    }
+
+    #[test]
+    fn test_flags_macro_defaults_switch_to_false() {
+        let app = AppFlags::from_args(vec!["input.rs".to_string()].into_iter())
+            .expect("from_args should succeed");
+
+        assert!(!app.verbose);
+        assert!(app.include.is_empty());
+    }
+
+    #[test]
+    fn test_flags_macro_missing_required_positional_errors() {
+        let err = AppFlags::from_args(vec!["-v".to_string()].into_iter()).unwrap_err();
+
+        assert!(matches!(err, FlagError::MissingArgument { flag } if flag == "input"));
+    }
 }
 